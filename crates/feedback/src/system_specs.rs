@@ -20,7 +20,7 @@ impl SystemSpecs {
     pub fn new(cx: &AppContext) -> Task<Self> {
         let app_version = AppVersion::global(cx).to_string();
         let release_channel = ReleaseChannel::global(cx);
-        let os_name = Self::os_name();
+        let os_name = Self::read_os_name();
         let system = System::new_with_specifics(
             RefreshKind::new().with_memory(MemoryRefreshKind::everything()),
         );
@@ -34,7 +34,7 @@ impl SystemSpecs {
         };
 
         cx.background_executor().spawn(async move {
-            let os_version = Self::os_version();
+            let os_version = Self::read_os_version();
             SystemSpecs {
                 app_version,
                 release_channel: release_channel.display_name(),
@@ -47,7 +47,22 @@ impl SystemSpecs {
         })
     }
 
-    fn os_name() -> String {
+    /// The operating system name, as reported in the specs.
+    pub fn os_name(&self) -> String {
+        self.os_name.clone()
+    }
+
+    /// The operating system version, or `None` when it could not be
+    /// determined (the per-platform lookups report `"unknown"` on failure).
+    pub fn os_version(&self) -> Option<String> {
+        if self.os_version.contains("unknown") {
+            None
+        } else {
+            Some(self.os_version.clone())
+        }
+    }
+
+    fn read_os_name() -> String {
         #[cfg(target_os = "macos")]
         {
             "macOS".to_string()
@@ -64,7 +79,7 @@ impl SystemSpecs {
     }
 
     /// Note: This might do blocking IO! Only call from background threads
-    fn os_version() -> String {
+    fn read_os_version() -> String {
         #[cfg(target_os = "macos")]
         {
             use cocoa::base::nil;
@@ -127,7 +142,6 @@ impl SystemSpecs {
             }
         }
     }
-
 }
 
 impl Display for SystemSpecs {
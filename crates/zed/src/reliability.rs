@@ -1,13 +1,15 @@
 use backtrace::Backtrace;
 use chrono::Utc;
+use feedback::system_specs::SystemSpecs;
 use gpui::{AppContext, SemanticVersion};
 
 use http::HttpClientWithUrl;
+use release_channel::AppVersion;
 use release_channel::ReleaseChannel;
 use release_channel::RELEASE_CHANNEL;
 use std::{
     env,
-    sync::{atomic::Ordering, Arc},
+    sync::{atomic::Ordering, Arc, OnceLock},
 };
 use std::{io::Write, panic, sync::atomic::AtomicU32, thread};
 use telemetry_events::LocationData;
@@ -16,6 +18,10 @@ use util::ResultExt;
 use crate::stdout_is_a_pty;
 static PANIC_COUNT: AtomicU32 = AtomicU32::new(0);
 
+/// A snapshot of the host's [`SystemSpecs`], computed once at startup so the
+/// synchronous panic hook can fill the OS identity without doing blocking IO.
+static SYSTEM_SPECS: OnceLock<SystemSpecs> = OnceLock::new();
+
 pub fn init_panic_hook(
     installation_id: Option<String>,
     app_version: SemanticVersion,
@@ -61,18 +67,25 @@ pub fn init_panic_hook(
         let mut backtrace = backtrace
             .frames()
             .iter()
-            .flat_map(|frame| {
-                frame
-                    .symbols()
-                    .iter()
-                    .filter_map(|frame| Some(format!("{:#}", frame.name()?)))
+            .flat_map(|frame| frame.symbols().iter())
+            .filter_map(|symbol| {
+                // Skip frames with no symbol name, matching the original
+                // behavior so stripped builds don't fill the report with
+                // placeholders. The alternate `SymbolName` formatter already
+                // demangles, so append just the source file:line when it is
+                // available to make the saved report directly readable.
+                let name = format!("{:#}", symbol.name()?);
+                Some(match (symbol.filename(), symbol.lineno()) {
+                    (Some(file), Some(line)) => format!("{name} ({}:{})", file.display(), line),
+                    _ => name,
+                })
             })
             .collect::<Vec<_>>();
 
         // Strip out leading stack frames for rust panic-handling.
         if let Some(ix) = backtrace
             .iter()
-            .position(|name| name == "rust_begin_unwind")
+            .position(|name| name.starts_with("rust_begin_unwind"))
         {
             backtrace.drain(0..=ix);
         }
@@ -86,8 +99,11 @@ pub fn init_panic_hook(
             }),
             app_version: app_version.to_string(),
             release_channel: RELEASE_CHANNEL.display_name().into(),
-            os_name: "".to_string(),
-            os_version: None,
+            os_name: SYSTEM_SPECS
+                .get()
+                .map(|specs| specs.os_name())
+                .unwrap_or_default(),
+            os_version: SYSTEM_SPECS.get().and_then(|specs| specs.os_version()),
             architecture: env::consts::ARCH.into(),
             panicked_on: Utc::now().timestamp_millis(),
             backtrace,
@@ -121,19 +137,69 @@ pub fn init_panic_hook(
 
 pub fn init(
     _http_client: Arc<HttpClientWithUrl>,
-    _installation_id: Option<String>,
-    _cx: &mut AppContext,
+    installation_id: Option<String>,
+    cx: &mut AppContext,
 ) {
-    #[cfg(target_os = "macos")]
-    monitor_main_thread_hangs(http_client.clone(), installation_id.clone(), cx);
+    // Compute the system specs on the background executor and stash them so the
+    // (synchronous) panic hook can report the OS identity without blocking IO.
+    let system_specs = SystemSpecs::new(cx);
+    cx.background_executor()
+        .spawn(async move {
+            SYSTEM_SPECS.set(system_specs.await).ok();
+        })
+        .detach();
+
+    // Old crash/hang reports are never cleaned up on crash, so prune them on
+    // the background executor to keep `logs_dir()` from growing unbounded.
+    cx.background_executor()
+        .spawn(async { clean_up_old_crash_reports() })
+        .detach();
+
+    monitor_main_thread_hangs(installation_id.clone(), cx);
 }
 
-#[cfg(target_os = "macos")]
-pub fn monitor_main_thread_hangs(
-    http_client: Arc<HttpClientWithUrl>,
-    installation_id: Option<String>,
-    cx: &AppContext,
-) {
+/// The number of most-recent `.panic`/`.hang` reports to retain in `logs_dir()`.
+const MAX_CRASH_REPORTS: usize = 30;
+/// An upper bound on the total size of the retained crash/hang reports.
+const MAX_CRASH_REPORTS_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Enumerates the locally saved `.panic`/`.hang` reports, keeps the
+/// [`MAX_CRASH_REPORTS`] most recent (also bounded by [`MAX_CRASH_REPORTS_BYTES`]
+/// in total) and deletes the rest. Intended to run off the launch path.
+fn clean_up_old_crash_reports() {
+    let Some(entries) = std::fs::read_dir(paths::logs_dir()).log_err() else {
+        return;
+    };
+
+    let mut reports = entries
+        .filter_map(|entry| entry.log_err())
+        .filter(|entry| {
+            matches!(
+                entry.path().extension().and_then(|ext| ext.to_str()),
+                Some("panic" | "hang")
+            )
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().log_err()?;
+            let modified = metadata.modified().log_err()?;
+            Some((entry.path(), modified, metadata.len()))
+        })
+        .collect::<Vec<_>>();
+
+    // Most recent first, so the tail of the list is what gets pruned.
+    reports.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+
+    let mut kept_bytes = 0;
+    for (ix, (path, _, len)) in reports.iter().enumerate() {
+        if ix >= MAX_CRASH_REPORTS || kept_bytes + len > MAX_CRASH_REPORTS_BYTES {
+            std::fs::remove_file(path).log_err();
+        } else {
+            kept_bytes += len;
+        }
+    }
+}
+
+pub fn monitor_main_thread_hangs(installation_id: Option<String>, cx: &AppContext) {
     // This is too noisy to ship to stable for now.
     if !matches!(
         ReleaseChannel::global(cx),
@@ -142,35 +208,46 @@ pub fn monitor_main_thread_hangs(
         return;
     }
 
-    use nix::sys::signal::{
-        sigaction, SaFlags, SigAction, SigHandler, SigSet,
-        Signal::{self, SIGUSR2},
-    };
-
     use parking_lot::Mutex;
 
-    use http::Method;
     use std::{
-        ffi::c_int,
         sync::{mpsc, OnceLock},
         time::Duration,
     };
     use telemetry_events::{BacktraceFrame, HangReport};
 
-    use nix::sys::pthread;
-
     let foreground_executor = cx.foreground_executor();
     let background_executor = cx.background_executor();
-    let telemetry_settings = *client::TelemetrySettings::get_global(cx);
+    let app_version = AppVersion::global(cx);
 
-    // Initialize SIGUSR2 handler to send a backrace to a channel.
+    // Initialize the handler to send a backtrace to a channel. On Unix the
+    // captured frames are `backtrace::Frame`s walked in the signal handler; on
+    // Windows they are the raw instruction pointers produced by `StackWalk64`,
+    // resolved later in the consumer.
     let (backtrace_tx, backtrace_rx) = mpsc::channel();
+    #[cfg(unix)]
     static BACKTRACE: Mutex<Vec<backtrace::Frame>> = Mutex::new(Vec::new());
+    #[cfg(windows)]
+    static BACKTRACE: Mutex<Vec<usize>> = Mutex::new(Vec::new());
     static BACKTRACE_SENDER: OnceLock<mpsc::Sender<()>> = OnceLock::new();
     BACKTRACE_SENDER.get_or_init(|| backtrace_tx);
     BACKTRACE.lock().reserve(100);
 
-    fn handle_backtrace_signal() {
+    // Install the platform-specific mechanism that, when the main thread is
+    // asked for a backtrace, captures its frames into `BACKTRACE` and then
+    // sends on `BACKTRACE_SENDER`. The returned handle is used below to request
+    // a capture once a hang is detected.
+    #[cfg(unix)]
+    let capture_main_thread = {
+        use nix::sys::pthread;
+        use nix::sys::signal::{
+            sigaction, SaFlags, SigAction, SigHandler, SigSet,
+            Signal::{self, SIGUSR2},
+        };
+        use std::ffi::c_int;
+
+        // The SIGUSR2 handler walks the main thread's stack in place. This is
+        // the same approach on macOS and Linux, so it is shared here.
         unsafe {
             extern "C" fn handle_sigusr2(_i: c_int) {
                 unsafe {
@@ -206,10 +283,140 @@ pub fn monitor_main_thread_hangs(
             )
             .log_err();
         }
-    }
 
-    handle_backtrace_signal();
-    let main_thread = pthread::pthread_self();
+        let main_thread = pthread::pthread_self();
+        move || {
+            pthread::pthread_kill(main_thread, SIGUSR2).log_err();
+        }
+    };
+
+    // Windows has no POSIX signals, so a dedicated watcher thread suspends the
+    // main thread, captures its context, walks the stack with `StackWalk64`,
+    // and resumes it, storing the raw instruction pointers it finds; the
+    // consumer resolves them into the same `BacktraceFrame` entries the signal
+    // path produces. Requesting a capture just wakes that thread.
+    #[cfg(windows)]
+    let capture_main_thread = {
+        use std::sync::mpsc as std_mpsc;
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::System::Threading::{
+            GetCurrentThreadId, OpenThread, ResumeThread, SuspendThread, THREAD_GET_CONTEXT,
+            THREAD_QUERY_INFORMATION, THREAD_SUSPEND_RESUME,
+        };
+
+        // A raw thread id is `Send`; the `HANDLE` is reopened inside the
+        // watcher thread so we never move a process-relative pseudo handle.
+        let main_thread_id = unsafe { GetCurrentThreadId() };
+
+        let (wake_tx, wake_rx) = std_mpsc::channel::<()>();
+        thread::Builder::new()
+            .name("main-thread-hang-watcher".into())
+            .spawn(move || {
+                let Some(main_thread) = (unsafe {
+                    OpenThread(
+                        THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT | THREAD_QUERY_INFORMATION,
+                        false,
+                        main_thread_id,
+                    )
+                })
+                .log_err() else {
+                    return;
+                };
+
+                while wake_rx.recv().is_ok() {
+                    capture_suspended_thread(main_thread);
+                    BACKTRACE_SENDER.get().unwrap().send(()).ok();
+                }
+
+                // Best-effort: reopened handles must be closed.
+                unsafe {
+                    windows::Win32::Foundation::CloseHandle(main_thread).log_err();
+                }
+            })
+            .log_err();
+
+        // Walks a suspended thread's stack into `BACKTRACE`. The stack walk is
+        // wired up for x86_64 only; on other Windows architectures the
+        // `STACKFRAME64` register seeding and the `IMAGE_FILE_MACHINE_AMD64`
+        // machine type would be wrong, so the capture is skipped rather than
+        // producing a garbage backtrace.
+        #[cfg(target_arch = "x86_64")]
+        fn capture_suspended_thread(thread: HANDLE) {
+            use windows::Win32::System::Diagnostics::Debug::{
+                RtlCaptureContext, StackWalk64, SymFunctionTableAccess64, SymGetModuleBase64,
+                ADDRESS64, ADDRESS_MODE, CONTEXT, STACKFRAME64,
+            };
+            use windows::Win32::System::Threading::GetCurrentProcess;
+
+            unsafe {
+                if SuspendThread(thread) == u32::MAX {
+                    return;
+                }
+
+                let mut context = std::mem::zeroed::<CONTEXT>();
+                RtlCaptureContext(&mut context);
+                if windows::Win32::System::Diagnostics::Debug::GetThreadContext(thread, &mut context)
+                    .is_err()
+                {
+                    ResumeThread(thread);
+                    return;
+                }
+
+                let mut frame = std::mem::zeroed::<STACKFRAME64>();
+                frame.AddrPC = ADDRESS64 {
+                    Offset: context.Rip,
+                    Mode: ADDRESS_MODE(3),
+                    ..Default::default()
+                };
+                frame.AddrFrame = ADDRESS64 {
+                    Offset: context.Rbp,
+                    Mode: ADDRESS_MODE(3),
+                    ..Default::default()
+                };
+                frame.AddrStack = ADDRESS64 {
+                    Offset: context.Rsp,
+                    Mode: ADDRESS_MODE(3),
+                    ..Default::default()
+                };
+
+                let process = GetCurrentProcess();
+                let mut bt = BACKTRACE.lock();
+                bt.clear();
+                while bt.len() < bt.capacity() {
+                    let walked = StackWalk64(
+                        windows::Win32::System::Diagnostics::Debug::IMAGE_FILE_MACHINE_AMD64.0
+                            as u32,
+                        process,
+                        thread,
+                        &mut frame,
+                        (&mut context as *mut CONTEXT).cast(),
+                        None,
+                        Some(SymFunctionTableAccess64),
+                        Some(SymGetModuleBase64),
+                        None,
+                    );
+                    if !walked.as_bool() || frame.AddrPC.Offset == 0 {
+                        break;
+                    }
+                    // Store the raw instruction pointer; it is symbolicated in
+                    // the consumer just like the signal path does.
+                    bt.push(frame.AddrPC.Offset as usize);
+                }
+                drop(bt);
+
+                ResumeThread(thread);
+            }
+        }
+
+        // On non-x86_64 Windows there is no stack walker, so leave `BACKTRACE`
+        // empty; the consumer just writes a `.hang` report without frames.
+        #[cfg(not(target_arch = "x86_64"))]
+        fn capture_suspended_thread(_thread: HANDLE) {}
+
+        move || {
+            wake_tx.send(()).ok();
+        }
+    };
 
     let (mut tx, mut rx) = futures::channel::mpsc::channel(3);
     foreground_executor
@@ -226,7 +433,7 @@ pub fn monitor_main_thread_hangs(
                         Ok(_) => continue,
                         Err(e) => {
                             if e.into_send_error().is_full() {
-                                pthread::pthread_kill(main_thread, SIGUSR2).log_err();
+                                capture_main_thread();
                             }
                             // Only detect the first hang
                             break;
@@ -236,4 +443,102 @@ pub fn monitor_main_thread_hangs(
             }
         })
         .detach();
+
+    // Consume the captured backtrace on the background executor and persist it
+    // locally. Since this fork is telemetry-free the report is written to
+    // `logs_dir()` as a `.hang` file rather than uploaded, mirroring how
+    // `init_panic_hook` writes `.panic` files.
+    background_executor
+        .spawn(async move {
+            loop {
+                // Wait for a hang to be signaled. A receive error means the
+                // sender was dropped, so there is nothing left to report.
+                if backtrace_rx.recv().is_err() {
+                    break;
+                }
+
+                #[cfg(unix)]
+                let mut hang_backtrace = BACKTRACE
+                    .lock()
+                    .iter()
+                    .map(|frame| {
+                        let mut symbols = Vec::new();
+                        backtrace::resolve(frame.ip(), |symbol| {
+                            if let Some(name) = symbol.name() {
+                                symbols.push(format!("{:#}", name));
+                            }
+                        });
+                        BacktraceFrame {
+                            ip: frame.ip() as usize,
+                            symbol_addr: frame.symbol_address() as usize,
+                            base: frame.module_base_address().map(|addr| addr as usize),
+                            symbols,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                #[cfg(windows)]
+                let mut hang_backtrace = BACKTRACE
+                    .lock()
+                    .iter()
+                    .map(|&ip| {
+                        let mut symbols = Vec::new();
+                        backtrace::resolve(ip as *mut _, |symbol| {
+                            if let Some(name) = symbol.name() {
+                                symbols.push(format!("{:#}", name));
+                            }
+                        });
+                        BacktraceFrame {
+                            ip,
+                            symbol_addr: ip,
+                            base: None,
+                            symbols,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                // Best-effort: strip leading stack frames belonging to the
+                // capture machinery itself (the SIGUSR2 handler on Unix). This
+                // only works when symbols resolve; on symbol-stripped Nightly/
+                // Preview builds the handler frames may be kept, which is
+                // harmless for offline triage.
+                if let Some(ix) = hang_backtrace
+                    .iter()
+                    .position(|frame| frame.symbols.iter().any(|s| s.contains("handle_sigusr2")))
+                {
+                    hang_backtrace.drain(0..=ix);
+                }
+
+                // Source the OS identity from the same startup snapshot the
+                // panic hook uses, so panic and hang reports agree.
+                let specs = SYSTEM_SPECS.get();
+                // Note: the request also asked for the duration since the last
+                // foreground heartbeat, but `telemetry_events::HangReport` has
+                // no field to carry it, so it cannot be recorded without an
+                // upstream schema change.
+                let report = HangReport {
+                    backtrace: hang_backtrace,
+                    app_version: Some(app_version),
+                    os_name: specs.map(|specs| specs.os_name()).unwrap_or_default(),
+                    os_version: specs.and_then(|specs| specs.os_version()),
+                    architecture: env::consts::ARCH.into(),
+                    installation_id: installation_id.clone(),
+                };
+
+                if let Some(report_json) = serde_json::to_string(&report).log_err() {
+                    let timestamp = Utc::now().format("%Y_%m_%d %H_%M_%S").to_string();
+                    let hang_file_path = paths::logs_dir().join(format!("zed-{timestamp}.hang"));
+                    let hang_file = std::fs::OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(&hang_file_path)
+                        .log_err();
+                    if let Some(mut hang_file) = hang_file {
+                        writeln!(&mut hang_file, "{report_json}").log_err();
+                        hang_file.flush().log_err();
+                    }
+                }
+            }
+        })
+        .detach();
 }